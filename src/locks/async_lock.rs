@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use crate::Timeout;
+
+use super::{LockGuard, LockImpl};
+
+/// Wraps any [`LockImpl`] so it can be awaited from an async task instead of blocking the
+/// calling thread, letting the primitives be used inside `tokio`/`async-std` IPC servers
+/// without dedicating a thread per pending lock.
+///
+/// The underlying OS handle is process-shared, but the waiters registered here are purely
+/// local: a task contending with another task *in this process, through this `AsyncLock`* is
+/// woken directly when the holder's guard is dropped. Only when the current holder is *not*
+/// one of this `AsyncLock`'s own guards (a different process, or the same `LockImpl` locked
+/// directly without going through this adapter) does a pending `lock()` call fall back to a
+/// single background backoff timer, since there is no local release event to wait on in that
+/// case.
+pub struct AsyncLock<'a> {
+    lock: &'a dyn LockImpl,
+    waiters: StdMutex<VecDeque<Waker>>,
+    // True while an `AsyncLockGuard` issued by this `AsyncLock` is outstanding. While set, a
+    // pending poll knows its waker will be woken directly by that guard's `Drop` and skips the
+    // backoff timer.
+    locally_held: AtomicBool,
+}
+
+impl<'a> AsyncLock<'a> {
+    pub fn new(lock: &'a dyn LockImpl) -> Self {
+        Self {
+            lock,
+            waiters: StdMutex::new(VecDeque::new()),
+            locally_held: AtomicBool::new(false),
+        }
+    }
+
+    /// Acquires the lock, yielding to other tasks while it is contended.
+    pub fn lock(&self) -> LockFuture<'_, 'a> {
+        LockFuture {
+            async_lock: self,
+            backoff_timer: None,
+        }
+    }
+
+    fn wake_next(&self) {
+        if let Some(waker) = self.waiters.lock().unwrap().pop_front() {
+            waker.wake();
+        }
+    }
+}
+
+/// Result of awaiting [`AsyncLock::lock`]. Mirrors [`super::LockResult`], but the guard
+/// wakes the next locally-queued waiter on drop instead of merely releasing the OS lock.
+pub enum AsyncLockResult<'f, 'a> {
+    Ok(AsyncLockGuard<'f, 'a>),
+    Abandoned(AsyncLockGuard<'f, 'a>),
+    Failed(Box<dyn std::error::Error>),
+}
+
+/// Future returned by [`AsyncLock::lock`]
+pub struct LockFuture<'f, 'a> {
+    async_lock: &'f AsyncLock<'a>,
+    // Set on the first `Poll::Pending` that finds the lock not held by one of this
+    // `AsyncLock`'s own guards; stays `None` while only local guards are in the way. Its flag is flipped on `Drop` so the spawned thread
+    // stops re-waking a future nobody is polling anymore, instead of leaking for its
+    // lifetime.
+    backoff_timer: Option<Arc<AtomicBool>>,
+}
+
+impl<'f, 'a> Drop for LockFuture<'f, 'a> {
+    fn drop(&mut self) {
+        if let Some(stop) = &self.backoff_timer {
+            stop.store(true, Ordering::Release);
+        }
+    }
+}
+
+impl<'f, 'a> Future for LockFuture<'f, 'a> {
+    type Output = AsyncLockResult<'f, 'a>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.async_lock.lock.try_lock(Timeout::Val(Duration::from_secs(0))) {
+            super::LockResult::Ok(guard) => {
+                self.async_lock.locally_held.store(true, Ordering::Release);
+                Poll::Ready(AsyncLockResult::Ok(AsyncLockGuard {
+                    inner: Some(guard),
+                    async_lock: self.async_lock,
+                }))
+            }
+            super::LockResult::Abandoned(guard) => {
+                self.async_lock.locally_held.store(true, Ordering::Release);
+                Poll::Ready(AsyncLockResult::Abandoned(AsyncLockGuard {
+                    inner: Some(guard),
+                    async_lock: self.async_lock,
+                }))
+            }
+            // A hard error from the underlying lock, as opposed to ordinary contention -
+            // surface it instead of retrying forever.
+            super::LockResult::Failed(err) => Poll::Ready(AsyncLockResult::Failed(err)),
+            super::LockResult::WouldBlock => {
+                // De-duplicate against whatever this future already registered; repeated
+                // polls of the same task would otherwise pile up stale wakers that
+                // `wake_next` would drain one at a time against already-completed tasks.
+                {
+                    let mut waiters = self.async_lock.waiters.lock().unwrap();
+                    if !waiters.iter().any(|w| w.will_wake(cx.waker())) {
+                        waiters.push_back(cx.waker().clone());
+                    }
+                }
+
+                // If the current holder is one of this `AsyncLock`'s own guards, its `Drop`
+                // will pop and wake our waker directly - no need to poll for progress. Only
+                // when the holder is outside this adapter's view (a different process, or
+                // the same `LockImpl` locked directly) do we fall back to a background
+                // backoff timer, and only one per pending call rather than one per poll.
+                if !self.async_lock.locally_held.load(Ordering::Acquire) && self.backoff_timer.is_none() {
+                    let stop = Arc::new(AtomicBool::new(false));
+                    self.backoff_timer = Some(stop.clone());
+                    let waker = cx.waker().clone();
+                    std::thread::spawn(move || {
+                        let mut backoff = Duration::from_micros(100);
+                        while !stop.load(Ordering::Acquire) {
+                            std::thread::sleep(backoff);
+                            if stop.load(Ordering::Acquire) {
+                                break;
+                            }
+                            waker.wake_by_ref();
+                            backoff = (backoff * 2).min(Duration::from_millis(10));
+                        }
+                    });
+                }
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Guard returned by a resolved [`LockFuture`]. Releases the underlying lock and wakes the
+/// next locally-queued waiter on `Drop`.
+pub struct AsyncLockGuard<'f, 'a> {
+    inner: Option<LockGuard<'a>>,
+    async_lock: &'f AsyncLock<'a>,
+}
+
+impl<'f, 'a> Drop for AsyncLockGuard<'f, 'a> {
+    fn drop(&mut self) {
+        self.inner.take();
+        self.async_lock.locally_held.store(false, Ordering::Release);
+        self.async_lock.wake_next();
+    }
+}
+
+impl<'f, 'a> Deref for AsyncLockGuard<'f, 'a> {
+    type Target = *mut u8;
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref().unwrap()
+    }
+}
+
+impl<'f, 'a> DerefMut for AsyncLockGuard<'f, 'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner.as_mut().unwrap()
+    }
+}
@@ -1,6 +1,14 @@
 use std::error::Error;
 use std::ops::{Deref, DerefMut};
 
+/// Futures-aware wrapper over [`LockImpl`]
+pub mod async_lock;
+pub use async_lock::AsyncLock;
+
+/// Shared-memory ticket lock that needs no named kernel object
+pub mod spin;
+pub use spin::TicketLock;
+
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
         mod windows;
@@ -18,12 +26,19 @@ pub use os::*;
 pub enum LockResult<'a> {
     Ok(LockGuard<'a>),
     Abandoned(LockGuard<'a>),
+    /// The lock is currently held by someone else and the requested timeout elapsed (or was
+    /// zero) without the caller being handed a guard. Unlike `Failed`, this is not an error: it is the
+    /// expected outcome of probing a contended lock, and callers like [`async_lock`] match on
+    /// it directly instead of sniffing an error message.
+    WouldBlock,
     Failed(Box<dyn Error>),
 }
 
 pub enum ReadLockResult<'a> {
     Ok(ReadLockGuard<'a>),
     Abandoned(ReadLockGuard<'a>),
+    /// See [`LockResult::WouldBlock`].
+    WouldBlock,
     Failed(Box<dyn Error>),
 }
 
@@ -32,6 +47,23 @@ impl <'a> LockResult<'a> {
         match self {
             LockResult::Ok(guard) => Ok(guard),
             LockResult::Abandoned(_) => Err(From::from("A thread holding the mutex has left it in a poisened state")),
+            LockResult::WouldBlock => Err(From::from("Timed out waiting for the lock")),
+            LockResult::Failed(err) => Err(err),
+        }
+    }
+
+    /// Accepts the guard even if it was abandoned, resetting the shared poison flag so that
+    /// subsequent lockers stop observing `Abandoned`. Mirrors std's `PoisonError::into_guard`,
+    /// for callers (e.g. a supervisor process) that have repaired the shared data structure's
+    /// invariants and want to declare the region healthy again.
+    pub fn recover(self) -> Result<LockGuard<'a>> {
+        match self {
+            LockResult::Ok(guard) => Ok(guard),
+            LockResult::Abandoned(guard) => {
+                guard.lock.clear_poison();
+                Ok(guard)
+            }
+            LockResult::WouldBlock => Err(From::from("Timed out waiting for the lock")),
             LockResult::Failed(err) => Err(err),
         }
     }
@@ -42,6 +74,7 @@ impl <'a> ReadLockResult<'a> {
         match self {
             ReadLockResult::Ok(guard) => Ok(guard),
             ReadLockResult::Abandoned(_) => Err(From::from("A thread holding the mutex has left it in a poisened state")),
+            ReadLockResult::WouldBlock => Err(From::from("Timed out waiting for the lock")),
             ReadLockResult::Failed(err) => Err(err),
         }
     }
@@ -75,11 +108,21 @@ pub trait LockImpl {
     /// Release the lock
     fn release(&self) -> Result<()>;
 
+    /// Returns whether a previous holder left the lock in an abandoned (poisoned) state,
+    /// without blocking to acquire it. Backed by an atomic flag stored in the shared
+    /// memory region, so this is race-free across processes.
+    fn is_abandoned(&self) -> bool;
+
+    /// Resets the shared poison flag set when a holder abandons the lock, declaring the
+    /// region healthy again. See [`LockResult::recover`] for the typical call site.
+    fn clear_poison(&self);
+
     /// Acquires the lock for read access only. This method uses `lock()` as a fallback
     fn rlock(&self) -> ReadLockResult {
         match self.lock() {
             LockResult::Ok(guard) => ReadLockResult::Ok(guard.into_read_guard()),
             LockResult::Abandoned(guard) => ReadLockResult::Abandoned(guard.into_read_guard()),
+            LockResult::WouldBlock => ReadLockResult::WouldBlock,
             LockResult::Failed(err) => ReadLockResult::Failed(err),
         }
     }
@@ -89,6 +132,7 @@ pub trait LockImpl {
         match self.try_lock(timeout) {
             LockResult::Ok(guard) => ReadLockResult::Ok(guard.into_read_guard()),
             LockResult::Abandoned(guard) => ReadLockResult::Abandoned(guard.into_read_guard()),
+            LockResult::WouldBlock => ReadLockResult::WouldBlock,
             LockResult::Failed(err) => ReadLockResult::Failed(err),
         }
     }
@@ -117,6 +161,17 @@ impl<'t> LockGuard<'t> {
         std::mem::forget(self);
         ReadLockGuard::new(inner_lock)
     }
+
+    /// Narrows this guard to a typed sub-pointer of the shared region, keeping the lock
+    /// held until the returned guard is dropped. Useful for handing out access to a single
+    /// field of a struct laid out in the shared memory segment without exposing the whole
+    /// region.
+    pub fn map<U, F: FnOnce(*mut u8) -> *mut U>(guard: Self, f: F) -> MappedLockGuard<'t, U> {
+        let ptr = f(*guard);
+        let lock = guard.lock;
+        std::mem::forget(guard);
+        MappedLockGuard { lock, ptr }
+    }
 }
 impl<'t> Deref for LockGuard<'t> {
     type Target = *mut u8;
@@ -138,6 +193,18 @@ impl<'t> ReadLockGuard<'t> {
     fn new(lock_impl: &'t dyn LockImpl) -> Self {
         Self { lock: lock_impl }
     }
+
+    /// Narrows this guard to a typed read-only sub-pointer of the shared region, keeping
+    /// the lock held until the returned guard is dropped.
+    pub fn map<U, F: FnOnce(*const u8) -> *const U>(
+        guard: Self,
+        f: F,
+    ) -> MappedReadLockGuard<'t, U> {
+        let ptr = f(*guard);
+        let lock = guard.lock;
+        std::mem::forget(guard);
+        MappedReadLockGuard { lock, ptr }
+    }
 }
 
 impl<'t> Drop for ReadLockGuard<'t> {
@@ -151,3 +218,145 @@ impl<'t> Deref for ReadLockGuard<'t> {
         unsafe { &*(self.lock.get_inner() as *mut *mut u8 as *const *const u8) }
     }
 }
+
+/// Used to wrap an acquired lock's data, narrowed to a typed sub-pointer via
+/// [`LockGuard::map`]. Lock is automatically released on `Drop`
+pub struct MappedLockGuard<'t, U> {
+    lock: &'t dyn LockImpl,
+    ptr: *mut U,
+}
+impl<'t, U> Drop for MappedLockGuard<'t, U> {
+    fn drop(&mut self) {
+        self.lock.release().unwrap();
+    }
+}
+impl<'t, U> Deref for MappedLockGuard<'t, U> {
+    type Target = *mut U;
+    fn deref(&self) -> &Self::Target {
+        &self.ptr
+    }
+}
+impl<'t, U> DerefMut for MappedLockGuard<'t, U> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.ptr
+    }
+}
+
+/// Used to wrap an acquired lock's read only data, narrowed to a typed sub-pointer via
+/// [`ReadLockGuard::map`]. Lock is automatically released on `Drop`
+pub struct MappedReadLockGuard<'t, U> {
+    lock: &'t dyn LockImpl,
+    ptr: *const U,
+}
+impl<'t, U> Drop for MappedReadLockGuard<'t, U> {
+    fn drop(&mut self) {
+        self.lock.release().unwrap();
+    }
+}
+impl<'t, U> Deref for MappedReadLockGuard<'t, U> {
+    type Target = *const U;
+    fn deref(&self) -> &Self::Target {
+        &self.ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+    /// Minimal in-process `LockImpl` for exercising the guard/recover plumbing without a real
+    /// OS handle or shared memory region. `release()` is counted so tests can assert it runs
+    /// exactly once, and `abandoned` can be flipped directly to simulate a holder that died
+    /// while serving, which no shipped impl can trigger in-process within this sandbox.
+    struct StubLock {
+        data: std::cell::UnsafeCell<*mut u8>,
+        release_count: AtomicUsize,
+        abandoned: AtomicBool,
+    }
+    unsafe impl Sync for StubLock {}
+
+    impl StubLock {
+        fn new(data: *mut u8) -> Self {
+            Self {
+                data: std::cell::UnsafeCell::new(data),
+                release_count: AtomicUsize::new(0),
+                abandoned: AtomicBool::new(false),
+            }
+        }
+    }
+
+    impl LockImpl for StubLock {
+        fn as_raw(&self) -> *mut std::ffi::c_void {
+            std::ptr::null_mut()
+        }
+
+        fn lock(&self) -> LockResult {
+            if self.abandoned.load(std::sync::atomic::Ordering::Acquire) {
+                LockResult::Abandoned(LockGuard::new(self))
+            } else {
+                LockResult::Ok(LockGuard::new(self))
+            }
+        }
+
+        fn try_lock(&self, _timeout: Timeout) -> LockResult {
+            self.lock()
+        }
+
+        fn release(&self) -> Result<()> {
+            self.release_count.fetch_add(1, std::sync::atomic::Ordering::AcqRel);
+            Ok(())
+        }
+
+        fn is_abandoned(&self) -> bool {
+            self.abandoned.load(std::sync::atomic::Ordering::Acquire)
+        }
+
+        fn clear_poison(&self) {
+            self.abandoned.store(false, std::sync::atomic::Ordering::Release);
+        }
+
+        unsafe fn get_inner(&self) -> &mut *mut u8 {
+            &mut *self.data.get()
+        }
+    }
+
+    #[test]
+    fn map_releases_exactly_once() {
+        let mut value: u8 = 42;
+        let lock = StubLock::new(&mut value as *mut u8);
+
+        let guard = lock.lock().deny_abandoned().unwrap();
+        let mapped = LockGuard::map(guard, |p| p);
+        assert_eq!(unsafe { *(*mapped) }, 42);
+        drop(mapped);
+
+        assert_eq!(lock.release_count.load(std::sync::atomic::Ordering::Acquire), 1);
+    }
+
+    #[test]
+    fn read_map_releases_exactly_once() {
+        let mut value: u8 = 7;
+        let lock = StubLock::new(&mut value as *mut u8);
+
+        let guard = lock.rlock().deny_abandoned().unwrap();
+        let mapped = ReadLockGuard::map(guard, |p| p);
+        assert_eq!(unsafe { *(*mapped) }, 7);
+        drop(mapped);
+
+        assert_eq!(lock.release_count.load(std::sync::atomic::Ordering::Acquire), 1);
+    }
+
+    #[test]
+    fn recover_clears_poison_and_yields_guard() {
+        let mut value: u8 = 0;
+        let lock = StubLock::new(&mut value as *mut u8);
+        lock.abandoned.store(true, std::sync::atomic::Ordering::Release);
+        assert!(lock.is_abandoned());
+
+        let guard = lock.lock().recover().expect("recover should accept an abandoned guard");
+        drop(guard);
+
+        assert!(!lock.is_abandoned());
+    }
+}
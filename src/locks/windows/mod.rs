@@ -2,6 +2,7 @@ use std::cell::UnsafeCell;
 use std::ffi::CString;
 use std::mem::size_of;
 use std::ptr::null_mut;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub const MUTEX_ALL_ACCESS: u32 = 0x1F0001;
 use winapi::{
@@ -9,27 +10,27 @@ use winapi::{
     um::{
         handleapi::CloseHandle,
         synchapi::{CreateMutexExA, ReleaseMutex, WaitForSingleObject, CREATE_MUTEX_INITIAL_OWNER},
-        winbase::{OpenMutexA, INFINITE, WAIT_ABANDONED, WAIT_OBJECT_0},
+        winbase::{OpenMutexA, INFINITE, WAIT_ABANDONED, WAIT_OBJECT_0, WAIT_TIMEOUT},
         winnt::{HANDLE, SYNCHRONIZE},
     },
 };
 
 use log::*;
 
-use crate::Result;
-use super::{LockGuard, LockImpl, LockInit};
+use crate::{Result, Timeout};
+use super::{LockGuard, LockImpl, LockInit, LockResult};
 
 pub struct Mutex {
     handle: HANDLE,
     data: UnsafeCell<*mut u8>,
+    // Points into the shared memory region, right after the mutex id, so every process
+    // that opens this mutex observes the same poison flag.
+    abandoned: *const AtomicBool,
 }
 
 impl LockInit for Mutex {
-    fn size_of() -> usize {
-        size_of::<u32>()
-    }
-    fn alignment() -> Option<u8> {
-        None
+    fn size_of(_addr: Option<*mut u8>) -> usize {
+        size_of::<u32>() + size_of::<AtomicBool>()
     }
 
     unsafe fn new(
@@ -56,17 +57,21 @@ impl LockInit for Mutex {
             );
         }
 
+        // Write the mutex id to the backing memory, followed by the (initially clear)
+        // abandoned/poison flag.
+        *(mem as *mut u32) = mutex_id;
+        let abandoned = mem.add(size_of::<u32>()) as *const AtomicBool;
+        (*abandoned).store(false, Ordering::Release);
+
         // Create our mutex struct
         let mutex = Box::new(Self {
             handle: mutex_handle,
             data: UnsafeCell::new(data),
+            abandoned,
         });
         mutex.release()?;
 
-        // Write the mutex id to the backing memory
-        *(mem as *mut u32) = mutex_id;
-
-        Ok((mutex, Self::size_of()))
+        Ok((mutex, Self::size_of(None)))
     }
 
     unsafe fn from_existing(
@@ -90,12 +95,14 @@ impl LockInit for Mutex {
             )));
         }
 
+        let abandoned = mem.add(size_of::<u32>()) as *const AtomicBool;
         let mutex = Box::new(Self {
             handle: mutex_handle,
             data: UnsafeCell::new(data),
+            abandoned,
         });
 
-        Ok((mutex, Self::size_of()))
+        Ok((mutex, Self::size_of(None)))
     }
 }
 
@@ -107,20 +114,39 @@ impl Drop for Mutex {
 }
 
 impl LockImpl for Mutex {
-    fn lock(&self) -> Result<LockGuard<'_>> {
-        let wait_res = unsafe { WaitForSingleObject(self.handle, INFINITE) };
+    fn as_raw(&self) -> *mut std::ffi::c_void {
+        self.handle as *mut std::ffi::c_void
+    }
+
+    fn lock(&self) -> LockResult {
+        self.try_lock(Timeout::Infinite)
+    }
+
+    fn try_lock(&self, timeout: Timeout) -> LockResult {
+        let millis = match timeout {
+            Timeout::Infinite => INFINITE,
+            Timeout::Val(duration) => duration.as_millis() as u32,
+        };
+        let wait_res = unsafe { WaitForSingleObject(self.handle, millis) };
         debug!("WaitForSingleObject(0x{:X})", self.handle as usize);
         if wait_res == WAIT_OBJECT_0 {
-            Ok(LockGuard::new(self))
+            LockResult::Ok(LockGuard::new(self))
         } else if wait_res == WAIT_ABANDONED {
-            panic!("A thread holding the mutex has left it in a poisened state");
+            // A thread holding the mutex died without releasing it; the guard is still
+            // handed to the caller (mirroring the OS semantics, which grant ownership to
+            // whoever waits it out), but flagged so callers can choose to `recover()` it.
+            unsafe { &*self.abandoned }.store(true, Ordering::Release);
+            LockResult::Abandoned(LockGuard::new(self))
+        } else if wait_res == WAIT_TIMEOUT {
+            LockResult::WouldBlock
         } else {
-            Err(From::from(format!(
+            LockResult::Failed(From::from(format!(
                 "Failed to aquire lock with value : 0x{:X}",
                 wait_res
             )))
         }
     }
+
     fn release(&self) -> Result<()> {
         debug!("ReleaseMutex(0x{:X})", self.handle as usize);
         if unsafe { ReleaseMutex(self.handle) } == 0 {
@@ -134,4 +160,12 @@ impl LockImpl for Mutex {
     unsafe fn get_inner(&self) -> &mut *mut u8 {
         &mut *self.data.get()
     }
+
+    fn is_abandoned(&self) -> bool {
+        unsafe { &*self.abandoned }.load(Ordering::Acquire)
+    }
+
+    fn clear_poison(&self) {
+        unsafe { &*self.abandoned }.store(false, Ordering::Release);
+    }
 }